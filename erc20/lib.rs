@@ -1,9 +1,56 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-#[ink::contract]
+use ink::env::Environment;
+
+/// Chain extension that lets the contract read a caller's native pallet
+/// balance, so token operations can be gated on it.
+#[ink::chain_extension]
+pub trait NativeBalanceExtension {
+    type ErrorCode = NativeBalanceErrorCode;
+
+    #[ink(extension = 1101)]
+    fn fetch_native_balance(account: AccountId) -> Balance;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum NativeBalanceErrorCode {
+    Failed,
+}
+
+impl ink::env::chain_extension::FromStatusCode for NativeBalanceErrorCode {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            1 => Err(Self::Failed),
+            _ => panic!("encountered unknown status code"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CustomEnvironment {}
+
+impl Environment for CustomEnvironment {
+    const MAX_EVENT_TOPICS: usize =
+        <ink::env::DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <ink::env::DefaultEnvironment as Environment>::AccountId;
+    type Balance = <ink::env::DefaultEnvironment as Environment>::Balance;
+    type Hash = <ink::env::DefaultEnvironment as Environment>::Hash;
+    type Timestamp = <ink::env::DefaultEnvironment as Environment>::Timestamp;
+    type BlockNumber = <ink::env::DefaultEnvironment as Environment>::BlockNumber;
+
+    type ChainExtension = NativeBalanceExtension;
+}
+
+#[ink::contract(env = crate::CustomEnvironment)]
 mod erc20 {
     // use ink::primitives::AccountId;
+    use ink::env::call::{build_call, utils::CallInput, DelegateCall, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
     use ink::storage::Mapping;
+    use scale::Encode;
 
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
@@ -14,6 +61,12 @@ mod erc20 {
         total_supply: Balance,
         balances: Mapping<AccountId, Balance>,
         allowances: Mapping<(AccountId, AccountId), Balance>,
+        lock_balance: Mapping<AccountId, Balance>,
+        lock_time: Mapping<AccountId, Timestamp>,
+        authority: AccountId,
+        used_nonces: Mapping<u128, ()>,
+        owner: AccountId,
+        impl_hash: Hash,
     }
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -21,6 +74,15 @@ mod erc20 {
     pub enum Error {
         BalanceTooLow,
         AllowanceTooLow,
+        StillLocked,
+        BadSignature,
+        ReceiptReused,
+        NotOwner,
+        BalanceOverflow,
+        InsufficientNativeBalance,
+        DelegateCallFailed,
+        LockDurationOverflow,
+        SetCodeFailed,
     }
 
     #[ink(event)]
@@ -41,6 +103,21 @@ mod erc20 {
         value: Balance,
     }
 
+    #[ink(event)]
+    pub struct Locked {
+        #[ink(topic)]
+        who: AccountId,
+        amount: Balance,
+        unlock_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Unlocked {
+        #[ink(topic)]
+        who: AccountId,
+        amount: Balance,
+    }
+
     type Result<T> = core::result::Result<T, Error>;
 
     impl Erc20 {
@@ -59,9 +136,11 @@ mod erc20 {
                 }
             );
 
-            Self { 
+            Self {
                 total_supply,
                 balances,
+                authority: receiver,
+                owner: receiver,
                 ..Default::default()
             }
         }
@@ -76,6 +155,11 @@ mod erc20 {
             self.balances.get(&who).unwrap_or_default()
         }
 
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get(&(owner, spender)).unwrap_or_default()
+        }
+
         pub fn transfer_from_to(&mut self, from: &AccountId, to: &AccountId, value: Balance) -> Result<()> {
             let balance_from = self.balance_of(*from);
             let balance_to: u128 = self.balance_of(*to);
@@ -84,8 +168,10 @@ mod erc20 {
                 return Err(Error::BalanceTooLow)
             }
 
+            let balance_to = balance_to.checked_add(value).ok_or(Error::BalanceOverflow)?;
+
             self.balances.insert(&from, &(balance_from - value));
-            self.balances.insert(&to, &(balance_to + value));
+            self.balances.insert(&to, &balance_to);
 
             self.env().emit_event(
                 Transfer {
@@ -117,6 +203,25 @@ mod erc20 {
             self.transfer_from_to(&from, &to, value)
         }
 
+        /// Transfers `value` to `to` only if the caller's native pallet
+        /// balance, as reported by the chain extension, is at least
+        /// `min_native`.
+        #[ink(message)]
+        pub fn transfer_if_funded(&mut self, to: AccountId, value: Balance, min_native: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let native_balance = self
+                .env()
+                .extension()
+                .fetch_native_balance(caller)
+                .unwrap_or_default();
+
+            if native_balance < min_native {
+                return Err(Error::InsufficientNativeBalance)
+            }
+
+            self.transfer_from_to(&caller, &to, value)
+        }
+
         #[ink(message)]
         pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
             let owner = self.env().caller();
@@ -131,6 +236,236 @@ mod erc20 {
             );
             Ok(())
         }
+
+        /// Creates `value` new tokens and credits them to `to`. Restricted to
+        /// `self.owner`.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+
+            let balance_to = self.balance_of(to);
+            let balance_to = balance_to.checked_add(value).ok_or(Error::BalanceOverflow)?;
+            let total_supply = self.total_supply.checked_add(value).ok_or(Error::BalanceOverflow)?;
+
+            self.total_supply = total_supply;
+            self.balances.insert(&to, &balance_to);
+
+            self.env().emit_event(
+                Transfer {
+                    from: None,
+                    to: Some(to),
+                    value,
+                }
+            );
+
+            Ok(())
+        }
+
+        /// Destroys `value` tokens held by `from`, lowering the total
+        /// supply. Restricted to `self.owner`.
+        #[ink(message)]
+        pub fn burn(&mut self, from: AccountId, value: Balance) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+
+            let balance_from = self.balance_of(from);
+            if value > balance_from {
+                return Err(Error::BalanceTooLow)
+            }
+
+            self.balances.insert(&from, &(balance_from - value));
+            self.total_supply -= value;
+
+            self.env().emit_event(
+                Transfer {
+                    from: Some(from),
+                    to: None,
+                    value,
+                }
+            );
+
+            Ok(())
+        }
+
+        /// Locks `amount` of the caller's balance for `duration` milliseconds,
+        /// similar to a lockdrop. The locked balance cannot be transferred
+        /// until it is unlocked.
+        #[ink(message)]
+        pub fn lock(&mut self, amount: Balance, duration: Timestamp) -> Result<()> {
+            let caller = self.env().caller();
+            if self.lock_balance.get(&caller).unwrap_or_default() > 0 {
+                return Err(Error::StillLocked)
+            }
+
+            let balance = self.balance_of(caller);
+            if amount > balance {
+                return Err(Error::BalanceTooLow)
+            }
+
+            let unlock_at = self
+                .env()
+                .block_timestamp()
+                .checked_add(duration)
+                .ok_or(Error::LockDurationOverflow)?;
+
+            self.balances.insert(&caller, &(balance - amount));
+            self.lock_balance.insert(&caller, &amount);
+            self.lock_time.insert(&caller, &unlock_at);
+
+            self.env().emit_event(
+                Locked {
+                    who: caller,
+                    amount,
+                    unlock_at,
+                }
+            );
+
+            Ok(())
+        }
+
+        /// Releases the caller's locked balance back into their spendable
+        /// balance once the lock duration has elapsed.
+        #[ink(message)]
+        pub fn unlock(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let unlock_at = self.lock_time.get(&caller).unwrap_or_default();
+
+            if self.env().block_timestamp() < unlock_at {
+                return Err(Error::StillLocked)
+            }
+
+            let amount = self.lock_balance.get(&caller).unwrap_or_default();
+            let balance = self.balance_of(caller);
+            self.balances.insert(&caller, &(balance + amount));
+
+            self.lock_balance.remove(&caller);
+            self.lock_time.remove(&caller);
+
+            self.env().emit_event(
+                Unlocked {
+                    who: caller,
+                    amount,
+                }
+            );
+
+            Ok(())
+        }
+
+        /// Redeems a receipt signed off-chain by `self.authority` (e.g. after a
+        /// burn on another chain) for freshly minted tokens. The `nonce` must
+        /// be unused; once redeemed it can never be replayed.
+        #[ink(message)]
+        pub fn mint_with_receipt(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            nonce: u128,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.used_nonces.contains(nonce) {
+                return Err(Error::ReceiptReused)
+            }
+
+            let message = (to, amount, nonce).encode();
+            let mut msg_hash = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Keccak256>(&message, &mut msg_hash);
+
+            let mut pubkey = [0u8; 33];
+            self.env()
+                .ecdsa_recover(&signature, &msg_hash, &mut pubkey)
+                .map_err(|_| Error::BadSignature)?;
+
+            let signer = Self::account_id_from_pubkey(&pubkey);
+            if signer != self.authority {
+                return Err(Error::BadSignature)
+            }
+
+            self.used_nonces.insert(nonce, &());
+
+            let balance_to = self.balance_of(to);
+            let balance_to = balance_to.checked_add(amount).ok_or(Error::BalanceOverflow)?;
+            let total_supply = self.total_supply.checked_add(amount).ok_or(Error::BalanceOverflow)?;
+
+            self.total_supply = total_supply;
+            self.balances.insert(&to, &balance_to);
+
+            self.env().emit_event(
+                Transfer {
+                    from: None,
+                    to: Some(to),
+                    value: amount,
+                }
+            );
+
+            Ok(())
+        }
+
+        /// Updates the bridge authority whose signature `mint_with_receipt`
+        /// accepts, so a real off-chain secp256k1 signer can be wired in
+        /// after deployment. Restricted to `self.owner`.
+        #[ink(message)]
+        pub fn set_authority(&mut self, authority: AccountId) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+
+            self.authority = authority;
+
+            Ok(())
+        }
+
+        /// Derives the `AccountId` that a recovered compressed ECDSA public
+        /// key corresponds to.
+        fn account_id_from_pubkey(pubkey: &[u8; 33]) -> AccountId {
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(pubkey, &mut output);
+            output.into()
+        }
+
+        /// Replaces this contract's code with `code_hash` and records it in
+        /// `impl_hash`; all other storage is left as it is. The new code MUST declare all of this contract's
+        /// fields, in the same declaration order, so the existing storage
+        /// layout keeps decoding correctly after the swap: `total_supply`,
+        /// `balances`, `allowances`, `lock_balance`, `lock_time`,
+        /// `authority`, `used_nonces`, `owner`, `impl_hash`.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::SetCodeFailed)?;
+            self.impl_hash = code_hash;
+
+            Ok(())
+        }
+
+        /// Forwards `input` to the code stored in `impl_hash` via a
+        /// delegate call, so that code runs against this contract's storage
+        /// rather than a storage of its own. Restricted to `self.owner`, same as
+        /// `set_code`, since an unrestricted delegate call would let anyone
+        /// execute arbitrary logic against this contract's storage.
+        #[ink(message)]
+        pub fn delegate(&mut self, selector: [u8; 4], input: Vec<u8>) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+
+            build_call::<Environment>()
+                .call_type(DelegateCall::new(self.impl_hash))
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(CallInput(&input)))
+                .returns::<()>()
+                .try_invoke()
+                .map_err(|_| Error::DelegateCallFailed)?
+                .map_err(|_| Error::DelegateCallFailed)?;
+
+            Ok(())
+        }
     }
 
     /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
@@ -188,6 +523,242 @@ mod erc20 {
             assert_eq!(res, Err(Error::BalanceTooLow));
         }
 
+        #[ink::test]
+        fn unlock_should_fail_before_expiry() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(erc20.lock(100, 500), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 10000 - 100);
+
+            let res = erc20.unlock();
+            assert_eq!(res, Err(Error::StillLocked));
+            assert_eq!(erc20.balance_of(accounts.alice), 10000 - 100);
+        }
+
+        #[ink::test]
+        fn unlock_should_work_after_expiry() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(erc20.lock(100, 500), Ok(()));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_500);
+            assert_eq!(erc20.unlock(), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 10000);
+        }
+
+        #[ink::test]
+        fn relock_cannot_shorten_existing_expiry() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(erc20.lock(100, 500), Ok(()));
+
+            // A second lock call right after the first must not reset the
+            // expiry to "now" and allow early withdrawal.
+            let res = erc20.lock(0, 0);
+            assert_eq!(res, Err(Error::StillLocked));
+
+            let res = erc20.unlock();
+            assert_eq!(res, Err(Error::StillLocked));
+            assert_eq!(erc20.balance_of(accounts.alice), 10000 - 100);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_500);
+            assert_eq!(erc20.unlock(), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 10000);
+        }
+
+        #[ink::test]
+        fn lock_rejects_an_overflowing_duration() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            let res = erc20.lock(100, Timestamp::MAX);
+            assert_eq!(res, Err(Error::LockDurationOverflow));
+            assert_eq!(erc20.balance_of(accounts.alice), 10000);
+        }
+
+        #[ink::test]
+        fn mint_with_receipt_rejects_bad_signature() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            let garbage_signature = [0u8; 65];
+            let res = erc20.mint_with_receipt(accounts.bob, 50, 1, garbage_signature);
+            assert_eq!(res, Err(Error::BadSignature));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn mint_with_receipt_rejects_reused_nonce() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            erc20.used_nonces.insert(1u128, &());
+            let garbage_signature = [0u8; 65];
+            let res = erc20.mint_with_receipt(accounts.bob, 50, 1, garbage_signature);
+            assert_eq!(res, Err(Error::ReceiptReused));
+        }
+
+        /// Signs a receipt with a real secp256k1 keypair and checks that
+        /// `mint_with_receipt` accepts it and credits the right account,
+        /// and that the same receipt cannot be redeemed twice.
+        #[ink::test]
+        fn mint_with_receipt_accepts_a_validly_signed_receipt() {
+            use secp256k1::{Message, Secp256k1, SecretKey};
+
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            let secp = Secp256k1::new();
+            let secret_key = SecretKey::from_slice(&[0x7a; 32]).expect("valid secret key");
+            let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+            let authority = Erc20::account_id_from_pubkey(&public_key.serialize());
+            assert_eq!(erc20.set_authority(authority), Ok(()));
+
+            let to = accounts.bob;
+            let amount = 750;
+            let nonce = 1u128;
+
+            let message = (to, amount, nonce).encode();
+            let mut msg_hash = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Keccak256>(&message, &mut msg_hash);
+            let msg = Message::from_slice(&msg_hash).expect("32-byte hash");
+
+            let (recovery_id, signature_bytes) = secp
+                .sign_ecdsa_recoverable(&msg, &secret_key)
+                .serialize_compact();
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&signature_bytes);
+            signature[64] = recovery_id.to_i32() as u8;
+
+            assert_eq!(erc20.mint_with_receipt(to, amount, nonce, signature), Ok(()));
+            assert_eq!(erc20.balance_of(to), amount);
+            assert_eq!(erc20.total_supply(), 10000 + amount);
+
+            assert_eq!(
+                erc20.mint_with_receipt(to, amount, nonce, signature),
+                Err(Error::ReceiptReused)
+            );
+        }
+
+        #[ink::test]
+        fn set_authority_should_fail_for_non_owner() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(erc20.set_authority(accounts.bob), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn allowance_reflects_approved_value() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(erc20.approve(accounts.bob, 200), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 200);
+        }
+
+        #[ink::test]
+        fn mint_should_work_for_owner() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.mint(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+            assert_eq!(erc20.total_supply(), 10500);
+        }
+
+        #[ink::test]
+        fn mint_should_fail_for_non_owner() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(erc20.mint(accounts.bob, 500), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn burn_should_work_for_owner() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.burn(accounts.alice, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 9000);
+            assert_eq!(erc20.total_supply(), 9000);
+        }
+
+        #[ink::test]
+        fn burn_should_fail_when_balance_too_low() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.burn(accounts.alice, 20000), Err(Error::BalanceTooLow));
+        }
+
+        #[ink::test]
+        fn burn_should_fail_for_non_owner() {
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(erc20.burn(accounts.alice, 500), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn transfer_if_funded_should_work_when_natively_funded() {
+            struct MockedExtension;
+            impl ink::env::test::ChainExtension for MockedExtension {
+                fn func_id(&self) -> u32 {
+                    1101
+                }
+
+                fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                    let native_balance: Balance = 1_000;
+                    scale::Encode::encode_to(&native_balance, output);
+                    0
+                }
+            }
+            ink::env::test::register_chain_extension(MockedExtension);
+
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.transfer_if_funded(accounts.bob, 100, 500), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn transfer_if_funded_should_fail_when_underfunded() {
+            struct MockedExtension;
+            impl ink::env::test::ChainExtension for MockedExtension {
+                fn func_id(&self) -> u32 {
+                    1101
+                }
+
+                fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                    let native_balance: Balance = 0;
+                    scale::Encode::encode_to(&native_balance, output);
+                    0
+                }
+            }
+            ink::env::test::register_chain_extension(MockedExtension);
+
+            let mut erc20 = Erc20::new(10000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            let res = erc20.transfer_if_funded(accounts.bob, 100, 500);
+            assert_eq!(res, Err(Error::InsufficientNativeBalance));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
     }
 
 
@@ -239,5 +810,46 @@ mod erc20 {
 
             Ok(())
         }
+
+        /// This only proves that calling `set_code` with a freshly uploaded
+        /// code hash of the *same* contract artifact does not wipe storage;
+        /// it does not exercise different logic actually running against
+        /// the old storage layout post-upgrade (that would require
+        /// uploading a second, distinct contract artifact).
+        #[ink_e2e::test]
+        async fn e2e_set_code_does_not_wipe_balances(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let total_suply = 123;
+            let constructor = Erc20Ref::new(total_suply);
+            let contract_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let alice_acc = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+
+            let new_code_hash = client
+                .upload("erc20", &ink_e2e::alice(), None)
+                .await
+                .expect("upload of new code failed")
+                .code_hash;
+
+            let set_code_msg = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.set_code(new_code_hash));
+            let set_code_result = client.call(
+                &ink_e2e::alice(), set_code_msg, 0, None
+            ).await;
+            assert!(set_code_result.is_ok());
+
+            let balance_of_msg = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.balance_of(alice_acc));
+            let balance_of_alice = client.call_dry_run(
+                &ink_e2e::alice(), &balance_of_msg, 0, None
+            ).await;
+
+            assert!(balance_of_alice.return_value() == total_suply);
+
+            Ok(())
+        }
     }
 }